@@ -0,0 +1,349 @@
+use anyhow::Result;
+use dotenv::dotenv;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::pipeline::FoundTask;
+
+/// Which CI provider a pipeline config targets.
+///
+/// Selected via the `--backend` CLI flag or the `backend:` key in
+/// `ciprobeconfig.yml`. Defaults to `AzureDevops` so existing configs keep
+/// working unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackendKind {
+    #[default]
+    AzureDevops,
+    GithubActions,
+    GitlabCi,
+}
+
+/// Abstracts over a CI provider's credential format, pipeline-file layout,
+/// and task/step extraction so the rest of ci-probe can stay provider-agnostic.
+///
+/// Each implementor owns its own `Credentials` shape (env var names, auth
+/// header format) since providers don't agree on either.
+pub trait CiBackend {
+    type Credentials;
+
+    /// Load credentials for this backend from a CLI argument, environment
+    /// variables, or a `.env` file, in that order of precedence.
+    fn load_credentials(cli_credentials: &Option<String>) -> Result<Self::Credentials>;
+
+    /// Find pipeline definition files for this backend under `root`.
+    fn discover_pipeline_files(&self, root: &Path) -> Result<Vec<PathBuf>>;
+
+    /// Parse a pipeline file's contents into the tasks/steps it references,
+    /// each with the version it was probed at and the source location of
+    /// that version.
+    fn extract_tasks(&self, content: &str) -> Result<Vec<FoundTask>>;
+}
+
+/// Object-safe view of a [`CiBackend`]'s pipeline discovery/extraction,
+/// omitting the credential-loading side (whose type differs per backend and
+/// so isn't dyn-compatible). This is what [`BackendKind`] routes through so
+/// `Config` can dispatch to the right provider's parser from its `backend`
+/// discriminator without matching on `BackendKind` at every call site.
+pub trait PipelineBackend {
+    fn discover_pipeline_files(&self, root: &Path) -> Result<Vec<PathBuf>>;
+    fn extract_tasks(&self, content: &str) -> Result<Vec<FoundTask>>;
+}
+
+impl<T: CiBackend> PipelineBackend for T {
+    fn discover_pipeline_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        CiBackend::discover_pipeline_files(self, root)
+    }
+
+    fn extract_tasks(&self, content: &str) -> Result<Vec<FoundTask>> {
+        CiBackend::extract_tasks(self, content)
+    }
+}
+
+impl BackendKind {
+    /// Resolves this discriminator to the concrete backend that knows how
+    /// to discover and parse its pipeline files.
+    pub fn pipeline_backend(self) -> Box<dyn PipelineBackend> {
+        match self {
+            BackendKind::AzureDevops => Box::new(AzureDevOps),
+            BackendKind::GithubActions => Box::new(GitHubActions),
+            BackendKind::GitlabCi => Box::new(GitLabCi),
+        }
+    }
+}
+
+fn load_env_credentials(
+    cli_credentials: &Option<String>,
+    parse: impl Fn(&str) -> Result<AzureCredentials>,
+    username_var: &str,
+    token_var: &str,
+) -> Result<AzureCredentials> {
+    if let Some(creds_str) = cli_credentials {
+        return parse(creds_str);
+    }
+
+    if let (Ok(username), Ok(token)) = (env::var(username_var), env::var(token_var)) {
+        return Ok(AzureCredentials { username, token });
+    }
+
+    dotenv().ok(); // Attempt to load from .env file
+    if let (Ok(username), Ok(token)) = (env::var(username_var), env::var(token_var)) {
+        Ok(AzureCredentials { username, token })
+    } else {
+        Err(anyhow::anyhow!(
+            "Credentials not found in environment or .env file"
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AzureCredentials {
+    pub username: String,
+    pub token: String,
+}
+
+impl AzureCredentials {
+    pub fn from_string(credentials: &str) -> Result<Self> {
+        let parts: Vec<&str> = credentials.split(':').collect();
+        if parts.len() != 2 {
+            return Err(anyhow::anyhow!(
+                "Invalid credentials format. Expected 'username:token'"
+            ));
+        }
+
+        Ok(AzureCredentials {
+            username: parts[0].to_string(),
+            token: parts[1].to_string(),
+        })
+    }
+
+    pub fn auth_header(&self) -> String {
+        let basic = format!("{}:{}", self.username, self.token);
+        format!("Basic {}", base64_encode(&basic))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHubCredentials {
+    pub token: String,
+}
+
+impl GitHubCredentials {
+    pub fn auth_header(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitLabCredentials {
+    pub token: String,
+}
+
+impl GitLabCredentials {
+    pub fn auth_header(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+}
+
+/// Minimal base64 encoder for the Azure DevOps basic-auth header, avoiding a
+/// dependency pull-in for a single call site.
+fn base64_encode(input: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let bytes = input.as_bytes();
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = *chunk.get(1).unwrap_or(&0);
+        let b2 = *chunk.get(2).unwrap_or(&0);
+
+        out.push(ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(ALPHABET[((b0 & 0x03) << 4 | b1 >> 4) as usize] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[((b1 & 0x0f) << 2 | b2 >> 6) as usize] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[(b2 & 0x3f) as usize] as char
+        } else {
+            '='
+        });
+    }
+
+    out
+}
+
+pub struct AzureDevOps;
+
+impl CiBackend for AzureDevOps {
+    type Credentials = AzureCredentials;
+
+    fn load_credentials(cli_credentials: &Option<String>) -> Result<Self::Credentials> {
+        load_env_credentials(
+            cli_credentials,
+            AzureCredentials::from_string,
+            "AZURE_USERNAME",
+            "AZURE_TOKEN",
+        )
+    }
+
+    fn discover_pipeline_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        discover_by_name(root, &["azure-pipelines.yml", "azure-pipelines.yaml"])
+    }
+
+    fn extract_tasks(&self, content: &str) -> Result<Vec<FoundTask>> {
+        crate::pipeline::azure::extract_tasks(content)
+    }
+}
+
+pub struct GitHubActions;
+
+impl CiBackend for GitHubActions {
+    type Credentials = GitHubCredentials;
+
+    fn load_credentials(cli_credentials: &Option<String>) -> Result<Self::Credentials> {
+        if let Some(token) = cli_credentials {
+            return Ok(GitHubCredentials {
+                token: token.clone(),
+            });
+        }
+
+        if let Ok(token) = env::var("GITHUB_TOKEN") {
+            return Ok(GitHubCredentials { token });
+        }
+
+        dotenv().ok();
+        env::var("GITHUB_TOKEN")
+            .map(|token| GitHubCredentials { token })
+            .map_err(|_| anyhow::anyhow!("GITHUB_TOKEN not found in environment or .env file"))
+    }
+
+    fn discover_pipeline_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        discover_workflow_files(&root.join(".github/workflows"))
+    }
+
+    fn extract_tasks(&self, content: &str) -> Result<Vec<FoundTask>> {
+        crate::pipeline::github::extract_tasks(content)
+    }
+}
+
+pub struct GitLabCi;
+
+impl CiBackend for GitLabCi {
+    type Credentials = GitLabCredentials;
+
+    fn load_credentials(cli_credentials: &Option<String>) -> Result<Self::Credentials> {
+        if let Some(token) = cli_credentials {
+            return Ok(GitLabCredentials {
+                token: token.clone(),
+            });
+        }
+
+        if let Ok(token) = env::var("GITLAB_TOKEN") {
+            return Ok(GitLabCredentials { token });
+        }
+
+        dotenv().ok();
+        env::var("GITLAB_TOKEN")
+            .map(|token| GitLabCredentials { token })
+            .map_err(|_| anyhow::anyhow!("GITLAB_TOKEN not found in environment or .env file"))
+    }
+
+    fn discover_pipeline_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        discover_by_name(root, &[".gitlab-ci.yml", ".gitlab-ci.yaml"])
+    }
+
+    fn extract_tasks(&self, content: &str) -> Result<Vec<FoundTask>> {
+        crate::pipeline::gitlab::extract_tasks(content)
+    }
+}
+
+fn discover_by_name(root: &Path, candidates: &[&str]) -> Result<Vec<PathBuf>> {
+    Ok(candidates
+        .iter()
+        .map(|name| root.join(name))
+        .filter(|path| path.exists())
+        .collect())
+}
+
+/// Enumerates `*.yml`/`*.yaml` files directly under `workflows_dir`, since
+/// GitHub Actions workflows are a directory of files rather than a single
+/// well-known pipeline path like the other backends.
+fn discover_workflow_files(workflows_dir: &Path) -> Result<Vec<PathBuf>> {
+    if !workflows_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(workflows_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_encode_matches_known_vectors() {
+        assert_eq!(base64_encode(""), "");
+        assert_eq!(base64_encode("f"), "Zg==");
+        assert_eq!(base64_encode("fo"), "Zm8=");
+        assert_eq!(base64_encode("foo"), "Zm9v");
+        assert_eq!(base64_encode("foobar"), "Zm9vYmFy");
+        assert_eq!(base64_encode("hello"), "aGVsbG8=");
+    }
+
+    #[test]
+    fn azure_credentials_auth_header_is_basic_base64() {
+        let creds = AzureCredentials {
+            username: "alice".to_string(),
+            token: "secret".to_string(),
+        };
+        assert_eq!(creds.auth_header(), "Basic YWxpY2U6c2VjcmV0");
+    }
+
+    #[test]
+    fn azure_credentials_from_string_rejects_missing_colon() {
+        assert!(AzureCredentials::from_string("no-colon-here").is_err());
+    }
+
+    #[test]
+    fn discover_workflow_files_enumerates_yml_and_yaml_only() {
+        let dir = env::temp_dir().join(format!(
+            "ci-probe-test-workflows-{:?}",
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join(".github/workflows")).unwrap();
+        let workflows = dir.join(".github/workflows");
+        std::fs::write(workflows.join("ci.yml"), "").unwrap();
+        std::fs::write(workflows.join("release.yaml"), "").unwrap();
+        std::fs::write(workflows.join("README.md"), "").unwrap();
+
+        let found = discover_workflow_files(&workflows).unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|p| {
+            let ext = p.extension().and_then(|e| e.to_str()).unwrap();
+            ext == "yml" || ext == "yaml"
+        }));
+    }
+
+    #[test]
+    fn discover_workflow_files_missing_dir_returns_empty() {
+        let missing = env::temp_dir().join("ci-probe-test-does-not-exist");
+        assert_eq!(discover_workflow_files(&missing).unwrap(), Vec::<PathBuf>::new());
+    }
+}
@@ -1,86 +1,76 @@
 use anyhow::Result;
-use dotenv::dotenv;
-use semver::Version;
+use miette::IntoDiagnostic;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::env;
 use std::path::Path;
 
-use crate::{GitVersionState, SupportedTask, TaskValidState};
+use crate::backend::{AzureCredentials, BackendKind};
+use crate::diagnostics::{self, CiProbeDiagnostic};
+use crate::pipeline::FoundTask;
+use crate::{remote, GitVersionState, SupportedTask, TaskValidState};
 
-#[derive(Debug, Clone)]
-pub struct Credentials {
-    pub username: String,
-    pub token: String,
-}
-
-impl Credentials {
-    pub fn load(cli_credentials: &Option<String>) -> Result<Self> {
-        if let Some(creds_str) = cli_credentials {
-            // Parse credentials from CLI argument
-            Self::from_string(creds_str)
-        } else if let (Ok(username), Ok(token)) =
-            (env::var("AZURE_USERNAME"), env::var("AZURE_TOKEN"))
-        {
-            // Load credentials from environment variables
-            Ok(Credentials { username, token })
-        } else {
-            dotenv().ok(); // Attempt to load from .env file
-            if let (Ok(username), Ok(token)) = (env::var("AZURE_USERNAME"), env::var("AZURE_TOKEN"))
-            {
-                Ok(Credentials { username, token })
-            } else {
-                Err(anyhow::anyhow!(
-                    "Credentials not found in environment or .env file"
-                ))
-            }
-        }
-    }
-
-    pub fn from_string(credentials: &str) -> Result<Self> {
-        let parts: Vec<&str> = credentials.split(':').collect();
-        if parts.len() != 2 {
-            return Err(anyhow::anyhow!(
-                "Invalid credentials format. Expected 'username:token'"
-            ));
-        }
-
-        Ok(Credentials {
-            username: parts[0].to_string(),
-            token: parts[1].to_string(),
-        })
-    }
+/// Normalizes versions like "1" or "1.0" to full semver ("1.0.0") so they
+/// parse with the `semver` crate.
+fn normalize_version(v: &str) -> Result<Version> {
+    let v = if v.chars().all(|c| c.is_ascii_digit()) {
+        format!("{}.0.0", v)
+    } else if v.matches('.').count() == 1 {
+        format!("{}.0", v)
+    } else {
+        v.to_string()
+    };
+    Version::parse(&v).map_err(|e| anyhow::anyhow!("Invalid version: {}", e))
 }
 
 pub trait VersionCompare {
     fn version_eq(&self, other: &str) -> bool;
+
+    /// Like [`VersionCompare::version_eq`], but `self` may also be a semver
+    /// requirement expression (`">=5.0.0, <6.0.0"`, `"^1.2"`, `"~3.1"`)
+    /// instead of a bare version. A bare version in `self` (e.g. `"5.0.0"`)
+    /// still requires exact equality with `other` — only a genuine
+    /// requirement expression is range-matched, since `VersionReq::parse`
+    /// would otherwise silently treat an exact pin as a caret range.
+    fn version_matches(&self, other: &str) -> bool;
 }
 
 impl VersionCompare for String {
     fn version_eq(&self, other: &str) -> bool {
-        // Normalize version strings
-        let normalize = |v: &str| -> Result<Version> {
-            // Handle versions like "1", "1.0", "1.0.0"
-            let v = if v.chars().all(|c| c.is_ascii_digit()) {
-                format!("{}.0.0", v)
-            } else if v.matches('.').count() == 1 {
-                format!("{}.0", v)
-            } else {
-                v.to_string()
-            };
-            Version::parse(&v).map_err(|e| anyhow::anyhow!("Invalid version: {}", e))
-        };
-
-        match (normalize(self), normalize(other)) {
+        match (normalize_version(self), normalize_version(other)) {
             (Ok(v1), Ok(v2)) => v1 == v2,
             _ => self == other, // Fallback to string comparison if parsing fails
         }
     }
+
+    fn version_matches(&self, other: &str) -> bool {
+        // A bare version (e.g. "5.0.0" or "5") also parses as a VersionReq
+        // (as the caret requirement "^5.0.0"), so bare versions must be
+        // checked for exact equality first or an exact pin would silently
+        // start accepting any compatible-range version.
+        if normalize_version(self).is_ok() {
+            return self.version_eq(other);
+        }
+
+        match (VersionReq::parse(self), normalize_version(other)) {
+            (Ok(req), Ok(version)) => req.matches(&version),
+            _ => self.version_eq(other),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default)]
+    pub backend: BackendKind,
     pub task_states: TaskStates,
+
+    /// Versions fetched live from the provider's installed-task catalog via
+    /// [`Config::refresh_from_remote`], for `--allow-installed`. Not
+    /// persisted; when present, `is_valid_version` consults this for any
+    /// task it lists and falls back to `task_states.other_tasks` for the rest.
+    #[serde(skip)]
+    pub installed_catalog: Option<HashMap<String, Vec<String>>>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -90,21 +80,98 @@ pub struct TaskStates {
 }
 
 impl Config {
-    pub fn load(path: Option<&Path>) -> Result<Self> {
+    /// Loads and validates `ciprobeconfig.yml`.
+    ///
+    /// Returns a [`miette::Result`] rather than the crate's usual
+    /// `anyhow::Result` so a malformed config surfaces as a span-pointing
+    /// [`CiProbeDiagnostic`] — wrapping it in `anyhow::Error` would discard
+    /// the `NamedSource`/`SourceSpan`/help text and leave only the flat
+    /// `#[error]` message.
+    pub fn load(path: Option<&Path>) -> miette::Result<Self> {
         let path = path.unwrap_or_else(|| Path::new("ciprobeconfig.yml"));
 
         if !path.exists() {
-            return Err(anyhow::anyhow!("Config file not found at {:?}", path));
+            return Err(miette::miette!("Config file not found at {:?}", path));
         }
 
-        let content = std::fs::read_to_string(path)?;
+        let content = std::fs::read_to_string(path).into_diagnostic()?;
+        let path_name = path.to_string_lossy().into_owned();
         let mut config: Config = serde_yaml::from_str(&content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse config file: {}", e))?;
+            .map_err(|e| diagnostics::config_parse_diagnostic(&path_name, content.clone(), &e))?;
 
         config.normalize_task_names();
         Ok(config)
     }
 
+    /// Checks a task's probed version against its allowed versions, same as
+    /// [`Config::is_valid_version`], but on failure returns a [`miette`]
+    /// diagnostic that underlines `found`'s exact span within `pipeline_path`'s
+    /// contents and lists the allowed versions as a help note.
+    pub fn check_task_version(
+        &self,
+        found: &FoundTask,
+        pipeline_path: &str,
+        pipeline_content: String,
+    ) -> Result<(), CiProbeDiagnostic> {
+        let task_name = match &found.task {
+            SupportedTask::Gitversion => "gitversion".to_string(),
+            SupportedTask::Default(name) => name.clone(),
+        };
+
+        if self.is_valid_version(&task_name, &found.version) {
+            return Ok(());
+        }
+
+        // Mirror is_valid_version: a "gitversion" task is checked against
+        // the gitversion states, so list those rather than other_tasks.
+        let allowed = if task_name.to_lowercase() == "gitversion" {
+            self.get_valid_states(&SupportedTask::Gitversion)
+        } else {
+            self.get_valid_states(&found.task)
+        };
+        Err(diagnostics::disallowed_task_version_diagnostic(
+            pipeline_path,
+            pipeline_content,
+            &task_name,
+            &found.version,
+            found.span,
+            &allowed,
+        ))
+    }
+
+    /// Extracts every task referenced in `pipeline_content` and checks each
+    /// one against this config's allow-list via [`Config::check_task_version`],
+    /// failing on the first disallowed version found.
+    pub fn validate_pipeline(
+        &self,
+        pipeline_path: &str,
+        pipeline_content: &str,
+    ) -> miette::Result<()> {
+        for found in self.extract_tasks(pipeline_content).into_diagnostic()? {
+            self.check_task_version(&found, pipeline_path, pipeline_content.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Which CI backend this config's pipelines target, for pipeline
+    /// discovery/extraction (see [`crate::backend::CiBackend`]).
+    pub fn backend(&self) -> BackendKind {
+        self.backend
+    }
+
+    /// Discovers this config's pipeline files, routed through the provider
+    /// selected by [`Config::backend`] (Azure DevOps YAML, GitHub Actions
+    /// workflows, or GitLab CI) instead of assuming Azure DevOps.
+    pub fn discover_pipeline_files(&self, root: &std::path::Path) -> Result<Vec<std::path::PathBuf>> {
+        self.backend.pipeline_backend().discover_pipeline_files(root)
+    }
+
+    /// Parses a pipeline file's contents into its tasks, routed through the
+    /// provider selected by [`Config::backend`].
+    pub fn extract_tasks(&self, content: &str) -> Result<Vec<FoundTask>> {
+        self.backend.pipeline_backend().extract_tasks(content)
+    }
+
     pub fn get_valid_states(&self, task: &SupportedTask) -> Vec<TaskValidState> {
         match task {
             SupportedTask::Gitversion => self
@@ -142,19 +209,51 @@ impl Config {
 
     pub fn is_valid_version(&self, task: &str, version: &str) -> bool {
         if task.to_lowercase() == "gitversion" {
-            self.task_states
-                .gitversion
-                .iter()
-                .any(|state| version == state.setup_version || version == state.execute_version)
+            self.task_states.gitversion.iter().any(|state| {
+                state.setup_version.version_matches(version)
+                    || state.execute_version.version_matches(version)
+            })
         } else {
-            self.task_states
-                .other_tasks
-                .get(task)
-                .map(|versions| versions.contains(&version.to_string()))
+            let versions = self
+                .installed_catalog
+                .as_ref()
+                .and_then(|catalog| catalog.get(task))
+                .or_else(|| self.task_states.other_tasks.get(task));
+
+            versions
+                .map(|versions| versions.iter().any(|allowed| allowed.version_matches(version)))
                 .unwrap_or(false)
         }
     }
 
+    /// Queries the Azure DevOps organization's installed-task catalog and
+    /// populates [`Config::installed_catalog`] with the versions actually
+    /// installed, for use with `--allow-installed`.
+    pub async fn refresh_from_remote(
+        &mut self,
+        organization_url: &str,
+        credentials: &AzureCredentials,
+    ) -> Result<()> {
+        let installed = remote::fetch_installed_task_versions(organization_url, credentials).await?;
+        self.installed_catalog = Some(installed);
+        Ok(())
+    }
+
+    /// Writes a normalized `ciprobeconfig.yml` back to `path`, replacing
+    /// `task_states.other_tasks` with the live [`Config::installed_catalog`]
+    /// (if populated by [`Config::refresh_from_remote`]); tasks that are only
+    /// in the old `other_tasks` are not kept. Used
+    /// by the `ci-probe update-config` subcommand.
+    pub fn write_normalized(&mut self, path: &Path) -> Result<()> {
+        if let Some(installed) = self.installed_catalog.take() {
+            self.task_states.other_tasks = installed;
+        }
+
+        let content = serde_yaml::to_string(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
     fn normalize_task_names(&mut self) {
         let normalized_tasks: HashMap<String, Vec<String>> = self
             .task_states
@@ -166,3 +265,78 @@ impl Config {
         self.task_states.other_tasks = normalized_tasks;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_pin_rejects_other_versions() {
+        let pin = String::from("5.0.0");
+        assert!(pin.version_matches("5.0.0"));
+        assert!(!pin.version_matches("5.3.1"));
+        assert!(!pin.version_matches("6.0.0"));
+    }
+
+    #[test]
+    fn bare_major_version_still_requires_exact_normalized_match() {
+        let pin = String::from("5");
+        assert!(pin.version_matches("5.0.0"));
+        assert!(!pin.version_matches("5.3.1"));
+    }
+
+    #[test]
+    fn requirement_expression_matches_range() {
+        let req = String::from(">=5.0.0, <6.0.0");
+        assert!(req.version_matches("5.3.1"));
+        assert!(!req.version_matches("6.0.0"));
+    }
+
+    #[test]
+    fn caret_and_tilde_requirements_match() {
+        assert!(String::from("^1.2").version_matches("1.5.0"));
+        assert!(!String::from("^1.2").version_matches("2.0.0"));
+        assert!(String::from("~3.1").version_matches("3.1.4"));
+        assert!(!String::from("~3.1").version_matches("3.2.0"));
+    }
+
+    #[test]
+    fn validate_pipeline_rejects_disallowed_task_version() {
+        let mut other_tasks = HashMap::new();
+        other_tasks.insert("gitversion".to_string(), vec![]);
+
+        let config = Config {
+            backend: BackendKind::AzureDevops,
+            task_states: TaskStates {
+                gitversion: vec![],
+                other_tasks,
+            },
+            installed_catalog: None,
+        };
+
+        let pipeline = "steps:\n  - task: GitVersion@5\n";
+        let result = config.validate_pipeline("azure-pipelines.yml", pipeline);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_pipeline_accepts_allowed_task_version() {
+        let config = Config {
+            backend: BackendKind::AzureDevops,
+            task_states: TaskStates {
+                gitversion: vec![crate::GitVersionState {
+                    setup_version: "5.0.0".to_string(),
+                    execute_version: "5.0.0".to_string(),
+                }],
+                other_tasks: HashMap::new(),
+            },
+            installed_catalog: None,
+        };
+
+        let pipeline = "steps:\n  - task: GitVersion@5\n";
+        let result = config.validate_pipeline("azure-pipelines.yml", pipeline);
+
+        assert!(result.is_ok());
+    }
+}
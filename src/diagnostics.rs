@@ -0,0 +1,121 @@
+//! Rich, span-pointing diagnostics for config and pipeline validation
+//! failures, built on `miette` so failures render the offending file, line,
+//! and byte span instead of a flat error string.
+
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use thiserror::Error;
+
+use crate::TaskValidState;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum CiProbeDiagnostic {
+    #[error("`ciprobeconfig.yml` could not be parsed")]
+    #[diagnostic(help("{message}"))]
+    ConfigParse {
+        #[source_code]
+        src: NamedSource<String>,
+        #[label("{message}")]
+        span: SourceSpan,
+        message: String,
+    },
+
+    #[error("task `{task}` uses disallowed version `{found}`")]
+    #[diagnostic(help("allowed versions: {}", allowed.join(", ")))]
+    DisallowedTaskVersion {
+        task: String,
+        found: String,
+        allowed: Vec<String>,
+        #[source_code]
+        src: NamedSource<String>,
+        #[label("this version is not in the allow-list")]
+        span: SourceSpan,
+    },
+}
+
+/// Builds a [`CiProbeDiagnostic::ConfigParse`] from a `serde_yaml` parse
+/// error, using its location's byte index as a one-byte span into `content`
+/// (an empty span at offset 0 when the error has no location) so the
+/// rendered diagnostic points at the offending position.
+pub fn config_parse_diagnostic(
+    path_name: &str,
+    content: String,
+    err: &serde_yaml::Error,
+) -> CiProbeDiagnostic {
+    let span = err
+        .location()
+        .map(|loc| SourceSpan::from((loc.index(), 1)))
+        .unwrap_or_else(|| SourceSpan::from((0, 0)));
+
+    CiProbeDiagnostic::ConfigParse {
+        src: NamedSource::new(path_name, content),
+        span,
+        message: err.to_string(),
+    }
+}
+
+/// Builds a [`CiProbeDiagnostic::DisallowedTaskVersion`], underlining
+/// `found` at `span` (the exact `(byte_offset, byte_len)` the caller found it
+/// at, e.g. from [`crate::pipeline::FoundTask::span`]) rather than
+/// re-searching `content` for it — a text search can match the wrong
+/// occurrence, or miss entirely once a bare version like `"1"` has been
+/// normalized to `"1.0.0"`.
+pub fn disallowed_task_version_diagnostic(
+    path_name: &str,
+    content: String,
+    task: &str,
+    found: &str,
+    span: (usize, usize),
+    allowed: &[TaskValidState],
+) -> CiProbeDiagnostic {
+    CiProbeDiagnostic::DisallowedTaskVersion {
+        task: task.to_string(),
+        found: found.to_string(),
+        allowed: allowed.iter().map(describe_valid_state).collect(),
+        src: NamedSource::new(path_name, content),
+        span: SourceSpan::from(span),
+    }
+}
+
+/// Renders a [`TaskValidState`] as a human-readable allowed-version entry,
+/// shared by the `miette` help text here and [`crate::report`]'s findings.
+pub fn describe_valid_state(state: &TaskValidState) -> String {
+    match state {
+        TaskValidState::Default(version) => version.clone(),
+        TaskValidState::Gitversion(state) => format!(
+            "setup={} execute={}",
+            state.setup_version, state.execute_version
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disallowed_task_version_diagnostic_underlines_caller_supplied_span() {
+        let content = "steps:\n  - task: GitVersion@5\n".to_string();
+        // "5" sits right before the trailing newline on line 2.
+        let span = (content.find('5').unwrap(), 1);
+
+        let diagnostic = disallowed_task_version_diagnostic(
+            "azure-pipelines.yml",
+            content.clone(),
+            "gitversion",
+            "5",
+            span,
+            &[],
+        );
+
+        match diagnostic {
+            CiProbeDiagnostic::DisallowedTaskVersion {
+                span: got_span, ..
+            } => {
+                let got_span: SourceSpan = got_span;
+                assert_eq!(got_span.offset(), span.0);
+                assert_eq!(got_span.len(), span.1);
+            }
+            _ => panic!("expected DisallowedTaskVersion"),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Per-backend pipeline file parsing: pulls the task/step/action references
+//! (name *and* probed version) out of a provider's YAML dialect, along with
+//! the exact source location of the version, so [`crate::backend::CiBackend`]
+//! impls can hand back a uniform `Vec<FoundTask>` that both
+//! `Config::is_valid_version` and [`crate::diagnostics`] can use directly.
+//!
+//! This scans line-by-line for `key: value` pairs instead of walking a
+//! `serde_yaml::Value` tree, because `Value` discards node positions — a
+//! spanned loader would be the long-term fix, but `serde_yaml` doesn't
+//! expose one, and re-finding the value by searching the raw text for it
+//! (as a naive "Value then string search" approach would) can match the
+//! wrong occurrence. Scanning lines directly keeps the line number and byte
+//! offset exact for the common single-line forms these backends produce, at
+//! the cost of not following YAML anchors/aliases or multi-line folded values.
+
+use crate::SupportedTask;
+
+/// A single task/step/image reference found in a pipeline file: which task,
+/// what version was probed, and exactly where that version sits in the
+/// source so a validation failure can point at it.
+#[derive(Debug, Clone)]
+pub struct FoundTask {
+    pub task: SupportedTask,
+    pub version: String,
+    /// 1-indexed line the version appears on.
+    pub line: usize,
+    /// `(byte_offset, byte_len)` of `version` within the scanned content.
+    pub span: (usize, usize),
+}
+
+/// A `key:` value found while scanning, with its line number and the byte
+/// offset where the (unquoted) value text starts.
+struct ScannedValue<'a> {
+    line: usize,
+    offset: usize,
+    text: &'a str,
+}
+
+/// Finds `key: value` lines — either standalone (`key: value`) or as a list
+/// item (`- key: value`) — and returns each value's raw text together with
+/// its 1-indexed line number and byte offset into `content`.
+fn scan_key_value_lines<'a>(content: &'a str, key: &str) -> Vec<ScannedValue<'a>> {
+    let solo = format!("{key}:");
+    let listed = format!("- {key}:");
+    let mut out = Vec::new();
+    let mut offset = 0usize;
+
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        let key_len = if trimmed.starts_with(&listed) {
+            listed.len()
+        } else if trimmed.starts_with(&solo) {
+            solo.len()
+        } else {
+            offset += line.len();
+            continue;
+        };
+
+        let after_key = &trimmed[key_len..];
+        let value_start_in_after = after_key.len() - after_key.trim_start().len();
+        let value = after_key.trim_start().trim_end_matches(['\r', '\n']).trim_end();
+
+        if !value.is_empty() {
+            out.push(ScannedValue {
+                line: idx + 1,
+                offset: offset + indent + key_len + value_start_in_after,
+                text: value,
+            });
+        }
+
+        offset += line.len();
+    }
+
+    out
+}
+
+/// Strips a single layer of matching quotes from `text`, adjusting `offset`
+/// to still point at the unquoted text.
+fn strip_quotes(text: &str, offset: usize) -> (&str, usize) {
+    let bytes = text.as_bytes();
+    if bytes.len() >= 2 && (bytes[0] == b'"' || bytes[0] == b'\'') && bytes[bytes.len() - 1] == bytes[0] {
+        (&text[1..text.len() - 1], offset + 1)
+    } else {
+        (text, offset)
+    }
+}
+
+/// Shared by the `azure`/`github` extractors: both use an `name@version`
+/// reference under a different key (`task` / `uses`).
+fn extract_at_ref_tasks(content: &str, key: &str) -> Vec<FoundTask> {
+    scan_key_value_lines(content, key)
+        .into_iter()
+        .filter_map(|scanned| {
+            let (value, value_offset) = strip_quotes(scanned.text, scanned.offset);
+            let (name, version) = value.split_once('@')?;
+            let version = version.trim_end();
+            Some(FoundTask {
+                task: SupportedTask::Default(name.to_lowercase()),
+                version: version.to_string(),
+                line: scanned.line,
+                span: (value_offset + name.len() + 1, version.len()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_support {
+    use super::FoundTask;
+
+    pub fn task_names(found: &[FoundTask]) -> Vec<String> {
+        found
+            .iter()
+            .map(|f| match &f.task {
+                crate::SupportedTask::Gitversion => "gitversion".to_string(),
+                crate::SupportedTask::Default(name) => name.clone(),
+            })
+            .collect()
+    }
+}
+
+pub mod azure {
+    use super::*;
+    use anyhow::Result;
+
+    /// Extracts `task: Name@Version` entries from an Azure DevOps pipeline YAML.
+    pub fn extract_tasks(content: &str) -> Result<Vec<FoundTask>> {
+        Ok(extract_at_ref_tasks(content, "task"))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::pipeline::test_support::task_names;
+
+        #[test]
+        fn extracts_task_name_and_version_from_nested_steps() {
+            let yaml = "\
+steps:
+  - task: GitVersion@5
+    inputs:
+      runtime: core
+  - script: echo hi
+";
+            let found = extract_tasks(yaml).unwrap();
+            assert_eq!(task_names(&found), vec!["gitversion"]);
+            assert_eq!(found[0].version, "5");
+            assert_eq!(found[0].line, 2);
+
+            let (start, len) = found[0].span;
+            assert_eq!(&yaml[start..start + len], "5");
+        }
+    }
+}
+
+pub mod github {
+    use super::*;
+    use anyhow::Result;
+
+    /// Extracts `uses: owner/action@ref` entries from a GitHub Actions workflow.
+    pub fn extract_tasks(content: &str) -> Result<Vec<FoundTask>> {
+        Ok(extract_at_ref_tasks(content, "uses"))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::pipeline::test_support::task_names;
+
+        #[test]
+        fn extracts_action_name_and_version_from_uses_step() {
+            let yaml = "\
+jobs:
+  build:
+    steps:
+      - uses: actions/checkout@v4
+      - run: echo hi
+";
+            let found = extract_tasks(yaml).unwrap();
+            assert_eq!(task_names(&found), vec!["actions/checkout"]);
+            assert_eq!(found[0].version, "v4");
+            assert_eq!(found[0].line, 4);
+
+            let (start, len) = found[0].span;
+            assert_eq!(&yaml[start..start + len], "v4");
+        }
+    }
+}
+
+pub mod gitlab {
+    use super::*;
+    use anyhow::Result;
+
+    /// Extracts `image:` tags and `include:` project/remote references from
+    /// a GitLab CI file.
+    ///
+    /// `include:` entries given as `project:`/`remote:`/`template:` are
+    /// returned name-only (`local:` entries and bare path strings are not
+    /// extracted) — the version-bearing `ref:` is a sibling key
+    /// in the same mapping rather than part of the same line, which this
+    /// line-oriented scan can't associate back to its `project:` without a
+    /// real structural parse, so `version` is left empty for these.
+    pub fn extract_tasks(content: &str) -> Result<Vec<FoundTask>> {
+        let mut tasks = extract_image_tasks(content);
+        tasks.extend(extract_include_tasks(content));
+        Ok(tasks)
+    }
+
+    fn extract_image_tasks(content: &str) -> Vec<FoundTask> {
+        scan_key_value_lines(content, "image")
+            .into_iter()
+            .filter_map(|scanned| {
+                let (value, value_offset) = strip_quotes(scanned.text, scanned.offset);
+                let tag_sep = parse_image_tag_sep(value)?;
+                let name = &value[..tag_sep];
+                let version = &value[tag_sep + 1..];
+                Some(FoundTask {
+                    task: SupportedTask::Default(name.to_lowercase()),
+                    version: version.to_string(),
+                    line: scanned.line,
+                    span: (value_offset + tag_sep + 1, version.len()),
+                })
+            })
+            .collect()
+    }
+
+    fn extract_include_tasks(content: &str) -> Vec<FoundTask> {
+        ["project", "remote", "template"]
+            .iter()
+            .flat_map(|key| scan_key_value_lines(content, key))
+            .map(|scanned| {
+                let (value, value_offset) = strip_quotes(scanned.text, scanned.offset);
+                FoundTask {
+                    task: SupportedTask::Default(value.to_lowercase()),
+                    version: String::new(),
+                    line: scanned.line,
+                    span: (value_offset, value.len()),
+                }
+            })
+            .collect()
+    }
+
+    /// Finds the byte index of the `:` that separates an `image:` value's
+    /// name from its tag, i.e. the last `:` after the last `/`, so a
+    /// registry host:port (`registry:5000/img:tag`) isn't mistaken for the
+    /// tag separator.
+    fn parse_image_tag_sep(image: &str) -> Option<usize> {
+        let name_start = image.rfind('/').map(|i| i + 1).unwrap_or(0);
+        Some(image[name_start..].rfind(':')? + name_start)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::pipeline::test_support::task_names;
+
+        #[test]
+        fn plain_image_extracts_name_and_version() {
+            let yaml = "image: node:18\n";
+            let found = extract_tasks(yaml).unwrap();
+            assert_eq!(task_names(&found), vec!["node"]);
+            assert_eq!(found[0].version, "18");
+
+            let (start, len) = found[0].span;
+            assert_eq!(&yaml[start..start + len], "18");
+        }
+
+        #[test]
+        fn registry_with_port_is_not_split_on_port_colon() {
+            let yaml = "image: registry:5000/team/img:1.2.3\n";
+            let found = extract_tasks(yaml).unwrap();
+            assert_eq!(task_names(&found), vec!["registry:5000/team/img"]);
+            assert_eq!(found[0].version, "1.2.3");
+
+            let (start, len) = found[0].span;
+            assert_eq!(&yaml[start..start + len], "1.2.3");
+        }
+
+        #[test]
+        fn include_project_and_remote_are_extracted_name_only() {
+            let yaml = "
+include:
+  - project: 'my-group/my-project'
+    ref: main
+    file: '/templates/build.yml'
+  - remote: 'https://example.com/ci.yml'
+";
+            let mut names = task_names(&extract_tasks(yaml).unwrap());
+            names.sort();
+            assert_eq!(
+                names,
+                vec!["https://example.com/ci.yml", "my-group/my-project"]
+            );
+        }
+    }
+}
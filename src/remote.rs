@@ -0,0 +1,69 @@
+//! Fetches the set of CI tasks an organization has installed, so
+//! `ciprobeconfig.yml`'s valid-state lists can be auto-populated instead of
+//! maintained by hand (see [`crate::config::Config::refresh_from_remote`]).
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::backend::AzureCredentials;
+
+const TASKS_API_VERSION: &str = "7.1";
+
+#[derive(Debug, Deserialize)]
+struct TaskListResponse {
+    value: Vec<InstalledTask>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstalledTask {
+    name: String,
+    version: InstalledTaskVersion,
+}
+
+#[derive(Debug, Deserialize)]
+struct InstalledTaskVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl std::fmt::Display for InstalledTaskVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Queries an Azure DevOps organization's installed-task catalog
+/// (`_apis/distributedtask/tasks`) and returns the versions installed per
+/// task, keyed by lowercased task name, mirroring `TaskStates::other_tasks`.
+pub async fn fetch_installed_task_versions(
+    organization_url: &str,
+    credentials: &AzureCredentials,
+) -> Result<HashMap<String, Vec<String>>> {
+    let url = format!(
+        "{}/_apis/distributedtask/tasks?api-version={}",
+        organization_url.trim_end_matches('/'),
+        TASKS_API_VERSION
+    );
+
+    let client = reqwest::Client::new();
+    let response: TaskListResponse = client
+        .get(url)
+        .header("Authorization", credentials.auth_header())
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+    for task in response.value {
+        versions
+            .entry(task.name.to_lowercase())
+            .or_default()
+            .push(task.version.to_string());
+    }
+
+    Ok(versions)
+}
@@ -0,0 +1,230 @@
+//! Structured, machine-readable reporting of probed tasks (`--format
+//! json|sarif`), for uploading findings to code-scanning dashboards instead
+//! of only printing a human-readable pass/fail.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::diagnostics::describe_valid_state;
+use crate::pipeline::FoundTask;
+use crate::SupportedTask;
+
+/// Output flavor selected by the `--format` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Sarif,
+}
+
+/// A single task occurrence found while scanning pipeline files, before it's
+/// checked against the config's valid-state lists.
+///
+/// Wraps a [`FoundTask`] rather than carrying its own `found_version`/`line`
+/// fields, since those are only ever populatable from a real scan — see
+/// [`ScannedTask::from_found`].
+#[derive(Debug, Clone)]
+pub struct ScannedTask {
+    pub found: FoundTask,
+    pub file: String,
+}
+
+impl ScannedTask {
+    pub fn from_found(found: FoundTask, file: impl Into<String>) -> Self {
+        ScannedTask {
+            found,
+            file: file.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskFinding {
+    pub task: String,
+    pub found_version: String,
+    pub file: String,
+    pub line: Option<usize>,
+    pub valid: bool,
+    pub allowed_versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ProbeReport {
+    pub tasks: Vec<TaskFinding>,
+}
+
+impl ProbeReport {
+    /// Builds a report by checking each scanned task against `config`'s
+    /// valid-state lists, the same lookup `Config::get_all_tasks` driven
+    /// scans use for the human-readable output.
+    pub fn build(config: &Config, scanned: &[ScannedTask]) -> Self {
+        let tasks = scanned
+            .iter()
+            .map(|scanned| {
+                let task_name = match &scanned.found.task {
+                    SupportedTask::Gitversion => "gitversion".to_string(),
+                    SupportedTask::Default(name) => name.clone(),
+                };
+                // "gitversion" is validated against the gitversion states, so
+                // report those as its allowed versions.
+                let lookup = if task_name.to_lowercase() == "gitversion" {
+                    SupportedTask::Gitversion
+                } else {
+                    scanned.found.task.clone()
+                };
+                let allowed_versions: Vec<String> = config
+                    .get_valid_states(&lookup)
+                    .iter()
+                    .map(describe_valid_state)
+                    .collect();
+
+                TaskFinding {
+                    task: task_name.clone(),
+                    found_version: scanned.found.version.clone(),
+                    file: scanned.file.clone(),
+                    line: Some(scanned.found.line),
+                    valid: config.is_valid_version(&task_name, &scanned.found.version),
+                    allowed_versions,
+                }
+            })
+            .collect();
+
+        ProbeReport { tasks }
+    }
+
+    /// Scans `pipeline_content` (from `file`) for tasks via
+    /// [`Config::extract_tasks`] and builds a report from the result — the
+    /// usual way to go from a pipeline file's raw text to a report without
+    /// hand-assembling [`ScannedTask`]s.
+    pub fn scan_pipeline(config: &Config, file: impl Into<String>, pipeline_content: &str) -> Result<Self> {
+        let file = file.into();
+        let scanned: Vec<ScannedTask> = config
+            .extract_tasks(pipeline_content)?
+            .into_iter()
+            .map(|found| ScannedTask::from_found(found, file.clone()))
+            .collect();
+
+        Ok(Self::build(config, &scanned))
+    }
+
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            ReportFormat::Sarif => Ok(serde_json::to_string_pretty(&self.to_sarif())?),
+        }
+    }
+
+    fn to_sarif(&self) -> SarifLog {
+        let results = self
+            .tasks
+            .iter()
+            .filter(|finding| !finding.valid)
+            .map(|finding| SarifResult {
+                rule_id: "ci-probe/disallowed-task-version".to_string(),
+                level: "error".to_string(),
+                message: SarifMessage {
+                    text: format!(
+                        "task `{}` uses disallowed version `{}` (allowed: {})",
+                        finding.task,
+                        finding.found_version,
+                        finding.allowed_versions.join(", ")
+                    ),
+                },
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation {
+                            uri: finding.file.clone(),
+                        },
+                        region: finding.line.map(|line| SarifRegion { start_line: line }),
+                    },
+                }],
+            })
+            .collect();
+
+        SarifLog {
+            schema: "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json".to_string(),
+            version: "2.1.0".to_string(),
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "ci-probe".to_string(),
+                        rules: vec![SarifRule {
+                            id: "ci-probe/disallowed-task-version".to_string(),
+                        }],
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: String,
+    version: String,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifDriver {
+    name: String,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRule {
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifResult {
+    #[serde(rename = "ruleId")]
+    rule_id: String,
+    level: String,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifLocation {
+    #[serde(rename = "physicalLocation")]
+    physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifPhysicalLocation {
+    #[serde(rename = "artifactLocation")]
+    artifact_location: SarifArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<SarifRegion>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRegion {
+    #[serde(rename = "startLine")]
+    start_line: usize,
+}